@@ -5,7 +5,23 @@ use crate::network::IoEvent;
 use serde::{Deserialize, Serialize};
 use tui::widgets::ListState;
 
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+const HISTORY_PATH: &str = "./history.json";
+
+pub fn item_key(item: &rss::Item) -> String {
+    match item.guid() {
+        Some(guid) => guid.value().to_string(),
+        None => format!(
+            "{}{}",
+            item.link().unwrap_or_default(),
+            item.title().unwrap_or_default()
+        ),
+    }
+}
 
 #[derive(Clone)]
 pub struct StatefulList<T> {
@@ -16,6 +32,25 @@ pub struct StatefulList<T> {
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Config {
     pub feeds: Vec<ConfigFeed>,
+    #[serde(default)]
+    pub open_command: Option<String>,
+    #[serde(default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+}
+
+fn default_refresh_interval_secs() -> u64 {
+    300
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+pub struct CacheEntry {
+    pub channel: rss::Channel,
+    pub fetched_at: Instant,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -24,6 +59,49 @@ pub struct ConfigFeed {
     pub url: String,
 }
 
+#[derive(Clone, PartialEq)]
+pub enum FetchState {
+    NotFetched,
+    Fetching,
+    Fetched,
+    Failed(String),
+}
+
+#[derive(Clone)]
+pub struct FeedItem {
+    pub feed: ConfigFeed,
+    pub state: FetchState,
+    pub item_keys: Vec<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct History {
+    pub read: HashSet<String>,
+}
+
+impl History {
+    pub fn load(path: &str) -> History {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &str) {
+        if let Ok(contents) = serde_json::to_string(self) {
+            let _ = fs::write(path, contents);
+        }
+    }
+
+    pub fn mark_read(&mut self, key: String) {
+        self.read.insert(key);
+    }
+
+    pub fn is_read(&self, key: &str) -> bool {
+        self.read.contains(key)
+    }
+}
+
 impl<T> StatefulList<T> {
     pub fn with_items(items: Vec<T>) -> StatefulList<T> {
         StatefulList {
@@ -33,6 +111,10 @@ impl<T> StatefulList<T> {
     }
 
     pub fn next(&mut self) {
+        if self.items.is_empty() {
+            self.state.select(None);
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -47,6 +129,10 @@ impl<T> StatefulList<T> {
     }
 
     pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            self.state.select(None);
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -70,8 +156,13 @@ pub enum SelectedView {
     NewsView,
 }
 
+pub enum NavigationStack {
+    Main,
+    Item,
+}
+
 pub struct App {
-    pub feeds: StatefulList<ConfigFeed>,
+    pub feeds: StatefulList<FeedItem>,
     pub news_data: Option<StatefulList<rss::Item>>,
     io_tx: Option<Sender<IoEvent>>,
     pub is_loading: bool,
@@ -79,24 +170,42 @@ pub struct App {
     pub news_index: usize,
     pub stacking: usize,
     pub config: Config,
+    pub history: History,
+    pub navigation_stack: NavigationStack,
+    pub status: Option<String>,
+    pub cache: HashMap<String, CacheEntry>,
 }
 
 impl App {
     pub fn new(config: Config, io_tx: Sender<IoEvent>) -> App {
+        let feeds = config
+            .feeds
+            .iter()
+            .map(|feed| FeedItem {
+                feed: feed.clone(),
+                state: FetchState::NotFetched,
+                item_keys: Vec::new(),
+            })
+            .collect();
         App {
             config: config.clone(),
-            feeds: StatefulList::with_items(config.feeds.clone()),
+            feeds: StatefulList::with_items(feeds),
             news_data: None,
             io_tx: Some(io_tx),
             is_loading: false,
             selected_view: SelectedView::FeedView,
             news_index: 0,
             stacking: 0,
+            history: History::load(HISTORY_PATH),
+            navigation_stack: NavigationStack::Main,
+            status: None,
+            cache: HashMap::new(),
         }
     }
 
     pub fn dispatch(&mut self, action: IoEvent) {
         self.is_loading = true;
+        self.status = None;
         if let Some(io_tx) = &self.io_tx {
             if let Err(e) = io_tx.send(action) {
                 self.is_loading = false;
@@ -116,29 +225,129 @@ impl App {
         self.dispatch(IoEvent::GetChannel(url));
     }
 
-    pub fn set_feed(&mut self, channel: rss::Channel) {
+    pub fn refresh_all(&mut self) {
+        self.dispatch(IoEvent::RefreshAll);
+    }
+
+    pub fn set_feed(&mut self, url: &str, channel: rss::Channel) {
+        self.cache_feed(url, channel.clone());
+        self.news_data = Some(StatefulList::with_items(channel.items().to_vec()));
+    }
+
+    pub fn cache_feed(&mut self, url: &str, channel: rss::Channel) {
+        self.update_item_keys(url, &channel);
+        self.cache.insert(
+            url.to_string(),
+            CacheEntry {
+                channel,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    pub fn set_news_data(&mut self, url: &str, channel: rss::Channel) {
+        self.update_item_keys(url, &channel);
         self.news_data = Some(StatefulList::with_items(channel.items().to_vec()));
     }
 
+    fn update_item_keys(&mut self, url: &str, channel: &rss::Channel) {
+        let keys: Vec<String> = channel.items().iter().map(item_key).collect();
+        if let Some(feed_item) = self.feeds.items.iter_mut().find(|i| i.feed.url == url) {
+            feed_item.item_keys = keys;
+        }
+    }
+
+    pub fn fresh_cached_channel(&self, url: &str, ttl: Duration) -> Option<rss::Channel> {
+        self.cache.get(url).and_then(|entry| {
+            if entry.fetched_at.elapsed() < ttl {
+                Some(entry.channel.clone())
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn set_feed_state(&mut self, url: &str, state: FetchState) {
+        if let Some(item) = self.feeds.items.iter_mut().find(|i| i.feed.url == url) {
+            item.state = state;
+        }
+    }
+
+    pub fn is_selected_feed(&self, url: &str) -> bool {
+        self.feeds
+            .state
+            .selected()
+            .map(|i| self.feeds.items[i].feed.url == url)
+            .unwrap_or(false)
+    }
+
+    pub fn reload_config(&mut self, new_config: Config) {
+        let mut existing: HashMap<String, FeedItem> = self
+            .feeds
+            .items
+            .drain(..)
+            .map(|item| (item.feed.url.clone(), item))
+            .collect();
+
+        let feeds: Vec<FeedItem> = new_config
+            .feeds
+            .iter()
+            .map(|feed| {
+                existing
+                    .remove(&feed.url)
+                    .unwrap_or_else(|| FeedItem {
+                        feed: feed.clone(),
+                        state: FetchState::NotFetched,
+                        item_keys: Vec::new(),
+                    })
+            })
+            .collect();
+
+        if let Some(i) = self.feeds.state.selected() {
+            if feeds.is_empty() {
+                self.feeds.state.select(None);
+            } else if i >= feeds.len() {
+                self.feeds.state.select(Some(feeds.len() - 1));
+            }
+        }
+
+        self.feeds.items = feeds;
+        self.config = new_config;
+    }
+
     pub fn view_feed_under_cursor(&mut self) {
         if let Some(index) = self.feeds.state.selected() {
-            self.get_channel(self.feeds.items[index].url.clone());
+            self.get_channel(self.feeds.items[index].feed.url.clone());
         }
     }
 
     pub fn back(&mut self) {
         self.news_index = 0;
         self.stacking -= 1;
+        self.navigation_stack = NavigationStack::Main;
     }
 
     pub fn view_news_under_cursor(&mut self) {
         self.stacking += 1;
-        match &self.news_data {
-            Some(data) => match data.state.selected() {
-                Some(i) => self.news_index = i,
-                None => self.news_index = 0,
-            },
-            None => {}
+        self.navigation_stack = NavigationStack::Item;
+        if let Some(data) = &self.news_data {
+            self.news_index = data.state.selected().unwrap_or(0);
+            self.history.mark_read(item_key(&data.items[self.news_index]));
+            self.history.save(HISTORY_PATH);
+        }
+    }
+
+    fn current_news_item(&self) -> Option<&rss::Item> {
+        let data = self.news_data.as_ref()?;
+        match self.navigation_stack {
+            NavigationStack::Item => data.items.get(self.news_index),
+            NavigationStack::Main => data.state.selected().and_then(|i| data.items.get(i)),
+        }
+    }
+
+    pub fn open_news_under_cursor(&mut self) {
+        if let Some(link) = self.current_news_item().and_then(|item| item.link()) {
+            self.dispatch(IoEvent::OpenItem(link.to_string()));
         }
     }
 }
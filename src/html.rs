@@ -0,0 +1,201 @@
+use tui::style::{Modifier, Style};
+use tui::text::{Span, Spans};
+
+pub fn render_description(html: &str) -> Vec<Spans<'static>> {
+    let bytes = html.as_bytes();
+    let len = html.len();
+    let mut lines: Vec<Vec<Span<'static>>> = vec![Vec::new()];
+    let mut bold = 0u32;
+    let mut italic = 0u32;
+    let mut link_href: Option<String> = None;
+    let mut i = 0usize;
+
+    while i < len {
+        if bytes[i] == b'<' {
+            match html[i..].find('>') {
+                Some(end) => {
+                    let tag = &html[i + 1..i + end];
+                    i += end + 1;
+                    handle_tag(tag, &mut lines, &mut bold, &mut italic, &mut link_href);
+                }
+                None => break,
+            }
+            continue;
+        }
+
+        let next_tag = html[i..].find('<').map(|p| i + p).unwrap_or(len);
+        let text = decode_entities(&html[i..next_tag]);
+        push_span(&mut lines, text, bold, italic, link_href.is_some());
+        i = next_tag;
+    }
+
+    lines.into_iter().map(Spans::from).collect()
+}
+
+fn handle_tag(
+    tag: &str,
+    lines: &mut Vec<Vec<Span<'static>>>,
+    bold: &mut u32,
+    italic: &mut u32,
+    link_href: &mut Option<String>,
+) {
+    let tag_lower = tag.to_lowercase();
+    let closing = tag_lower.starts_with('/');
+    let name = tag_lower
+        .trim_start_matches('/')
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .next()
+        .unwrap_or("");
+
+    match name {
+        "br" => lines.push(Vec::new()),
+        "p" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            if !lines.last().unwrap().is_empty() =>
+        {
+            lines.push(Vec::new());
+        }
+        "li" if !closing => {
+            if !lines.last().unwrap().is_empty() {
+                lines.push(Vec::new());
+            }
+            lines.last_mut().unwrap().push(Span::raw("• "));
+        }
+        "strong" | "b" => {
+            if closing {
+                *bold = bold.saturating_sub(1);
+            } else {
+                *bold += 1;
+            }
+        }
+        "em" | "i" => {
+            if closing {
+                *italic = italic.saturating_sub(1);
+            } else {
+                *italic += 1;
+            }
+        }
+        "a" => {
+            if closing {
+                if let Some(href) = link_href.take() {
+                    push_span(lines, format!(" ({})", href), *bold, *italic, false);
+                }
+            } else {
+                *link_href = extract_href(tag);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_span(
+    lines: &mut [Vec<Span<'static>>],
+    text: String,
+    bold: u32,
+    italic: u32,
+    underlined: bool,
+) {
+    if text.is_empty() {
+        return;
+    }
+    let mut modifier = Modifier::empty();
+    if bold > 0 {
+        modifier |= Modifier::BOLD;
+    }
+    if italic > 0 {
+        modifier |= Modifier::ITALIC;
+    }
+    if underlined {
+        modifier |= Modifier::UNDERLINED;
+    }
+    lines
+        .last_mut()
+        .unwrap()
+        .push(Span::styled(text, Style::default().add_modifier(modifier)));
+}
+
+fn extract_href(tag: &str) -> Option<String> {
+    let lower = tag.to_lowercase();
+    let attr_start = lower.find("href")? + "href".len();
+    let rest = tag[attr_start..].trim_start();
+    let rest = rest.strip_prefix('=')?.trim_start();
+    let quote = rest.chars().next()?;
+    if quote == '"' || quote == '\'' {
+        let end = rest[1..].find(quote)?;
+        Some(rest[1..1 + end].to_string())
+    } else {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        Some(rest[..end].to_string())
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&nbsp;", " ")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain_text(spans: &[Spans]) -> Vec<String> {
+        spans
+            .iter()
+            .map(|line| line.0.iter().map(|s| s.content.as_ref()).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_entities() {
+        let lines = render_description("Tom &amp; Jerry &lt;3&gt; &quot;fun&quot;");
+        assert_eq!(plain_text(&lines), vec!["Tom & Jerry <3> \"fun\""]);
+    }
+
+    #[test]
+    fn nested_tags_combine_modifiers() {
+        let lines = render_description("<strong>bold <em>and italic</em> still bold</strong>");
+        let and_italic = lines[0]
+            .0
+            .iter()
+            .find(|s| s.content.as_ref() == "and italic")
+            .unwrap();
+        assert!(and_italic.style.add_modifier.contains(Modifier::BOLD));
+        assert!(and_italic.style.add_modifier.contains(Modifier::ITALIC));
+
+        let still_bold = lines[0]
+            .0
+            .iter()
+            .find(|s| s.content.as_ref() == " still bold")
+            .unwrap();
+        assert!(still_bold.style.add_modifier.contains(Modifier::BOLD));
+        assert!(!still_bold.style.add_modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn unterminated_tag_does_not_panic() {
+        let lines = render_description("Some text <strong unterminated");
+        assert_eq!(plain_text(&lines), vec!["Some text "]);
+    }
+
+    #[test]
+    fn anchor_text_is_underlined_with_href_appended() {
+        let lines = render_description(r#"<a href="https://example.com">click</a>"#);
+        let link = lines[0]
+            .0
+            .iter()
+            .find(|s| s.content.as_ref() == "click")
+            .unwrap();
+        assert!(link.style.add_modifier.contains(Modifier::UNDERLINED));
+        assert_eq!(plain_text(&lines), vec!["click (https://example.com)"]);
+    }
+
+    #[test]
+    fn list_items_get_bullets_on_separate_lines() {
+        let lines = render_description("<ul><li>one</li><li>two</li></ul>");
+        assert_eq!(plain_text(&lines), vec!["• one", "• two"]);
+    }
+}
@@ -1,4 +1,5 @@
 mod app;
+mod html;
 mod network;
 
 extern crate crossterm;
@@ -6,23 +7,27 @@ extern crate rss;
 extern crate serde;
 extern crate tui;
 
-use app::{App, Config, NavigationStack, SelectedView};
+use app::{item_key, App, Config, FetchState, NavigationStack, SelectedView};
+use html::render_description;
 use crossterm::{
     event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        DisableMouseCapture, EnableMouseCapture, Event as CEvent, EventStream, KeyCode, KeyEvent,
+        KeyModifiers,
     },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use network::{IoEvent, Network};
+use notify::Watcher;
 
+use futures::StreamExt;
 use std::{
     fs, io,
     sync::Arc,
     thread,
-    time::{Duration, Instant},
+    time::Duration,
 };
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, Mutex};
 use tui::{
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout},
@@ -32,6 +37,17 @@ use tui::{
     Frame, Terminal,
 };
 
+const CONFIG_PATH: &str = "./config.json";
+
+pub enum Event {
+    Key(KeyEvent),
+    Resize(u16, u16),
+    Tick,
+    NetworkDone,
+    AutoRefresh,
+    ConfigReloaded(Config),
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     enable_raw_mode()?;
@@ -41,7 +57,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     let config_file =
-        fs::read_to_string("./config.json").expect("Something went wrong reading config file");
+        fs::read_to_string(CONFIG_PATH).expect("Something went wrong reading config file");
 
     let config: Config;
     match serde_json::from_str(&config_file) {
@@ -62,14 +78,19 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let tick_rate = Duration::from_millis(250);
+    let refresh_interval = Duration::from_secs(config.refresh_interval_secs.max(1));
     let (sync_io_tx, sync_io_rx) = std::sync::mpsc::channel::<IoEvent>();
+    let (event_tx, event_rx) = mpsc::channel::<Event>(100);
     let app = Arc::new(Mutex::new(App::new(config, sync_io_tx)));
     let cloned_app = Arc::clone(&app);
+    let network_event_tx = event_tx.clone();
     std::thread::spawn(move || {
-        let mut network = Network::new(&app);
+        let mut network = Network::new(&app, network_event_tx);
         start_tokio(sync_io_rx, &mut network);
     });
-    let _res = run_app(&mut terminal, &cloned_app, tick_rate).await?;
+    tokio::spawn(input_loop(event_tx.clone(), tick_rate, refresh_interval));
+    spawn_config_watcher(CONFIG_PATH.to_string(), event_tx);
+    let _res = run_app(&mut terminal, &cloned_app, event_rx).await?;
 
     disable_raw_mode()?;
     execute!(
@@ -89,71 +110,141 @@ async fn start_tokio<'a>(io_rx: std::sync::mpsc::Receiver<IoEvent>, network: &mu
     }
 }
 
+async fn input_loop(tx: mpsc::Sender<Event>, tick_rate: Duration, refresh_interval: Duration) {
+    let mut reader = EventStream::new();
+    let mut tick_timer = tokio::time::interval(tick_rate);
+    let mut refresh_timer = tokio::time::interval(refresh_interval);
+    loop {
+        tokio::select! {
+            _ = tick_timer.tick() => {
+                if tx.send(Event::Tick).await.is_err() {
+                    return;
+                }
+            }
+            _ = refresh_timer.tick() => {
+                if tx.send(Event::AutoRefresh).await.is_err() {
+                    return;
+                }
+            }
+            maybe_event = reader.next() => {
+                let event = match maybe_event {
+                    Some(Ok(CEvent::Key(key))) => Event::Key(key),
+                    Some(Ok(CEvent::Resize(width, height))) => Event::Resize(width, height),
+                    Some(Ok(_)) => continue,
+                    Some(Err(_)) | None => return,
+                };
+                if tx.send(event).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn spawn_config_watcher(path: String, event_tx: mpsc::Sender<Event>) {
+    std::thread::spawn(move || {
+        let (notify_tx, notify_rx) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = match notify::recommended_watcher(notify_tx) {
+            Ok(watcher) => watcher,
+            Err(_) => return,
+        };
+        if watcher
+            .watch(std::path::Path::new(&path), notify::RecursiveMode::NonRecursive)
+            .is_err()
+        {
+            return;
+        }
+
+        for result in notify_rx {
+            let modified = matches!(result, Ok(ref event) if event.kind.is_modify());
+            if !modified {
+                continue;
+            }
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let Ok(new_config) = serde_json::from_str::<Config>(&contents) else {
+                continue;
+            };
+            if event_tx.blocking_send(Event::ConfigReloaded(new_config)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
 async fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     app: &Arc<Mutex<App>>,
-    tick_rate: Duration,
+    mut rx: mpsc::Receiver<Event>,
 ) -> io::Result<()> {
-    let mut last_tick = Instant::now();
-    loop {
+    while let Some(event) = rx.recv().await {
         let mut app = app.lock().await;
-        terminal.draw(|mut f| ui(&mut f, &app))?;
-
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
-        if crossterm::event::poll(timeout)? {
-            let event = event::read()?;
-            match event {
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::CONTROL,
-                    code: KeyCode::Char('w'),
-                }) => app.switch_view(),
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::NONE,
-                    code: KeyCode::Char('q'),
-                }) => match app.navigation_stack {
-                    NavigationStack::Main => return Ok(()),
-                    NavigationStack::Item => app.back(),
-                },
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::NONE,
-                    code: KeyCode::Char('h'),
-                }) => app.feeds.unselect(),
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::NONE,
-                    code: KeyCode::Char('j'),
-                }) => match app.selected_view {
-                    SelectedView::FeedView => app.feeds.next(),
-                    SelectedView::NewsView => app.news_data.as_mut().unwrap().next(),
-                },
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::NONE,
-                    code: KeyCode::Char('k'),
-                }) => match app.selected_view {
-                    SelectedView::FeedView => app.feeds.previous(),
-                    SelectedView::NewsView => app.news_data.as_mut().unwrap().previous(),
-                },
-                Event::Key(KeyEvent {
-                    modifiers: KeyModifiers::NONE,
-                    code: KeyCode::Enter,
-                }) => match app.selected_view {
-                    SelectedView::FeedView => app.view_feed_under_cursor(),
-                    SelectedView::NewsView => app.view_news_under_cursor(),
-                },
-                _ => {}
-            }
-        }
-        if last_tick.elapsed() >= tick_rate {
-            last_tick = Instant::now();
+        match event {
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::CONTROL,
+                code: KeyCode::Char('w'),
+            }) => app.switch_view(),
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('q'),
+            }) => match app.navigation_stack {
+                NavigationStack::Main => return Ok(()),
+                NavigationStack::Item => app.back(),
+            },
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('h'),
+            }) => app.feeds.unselect(),
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('j'),
+            }) => match app.selected_view {
+                SelectedView::FeedView => app.feeds.next(),
+                SelectedView::NewsView => app.news_data.as_mut().unwrap().next(),
+            },
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('k'),
+            }) => match app.selected_view {
+                SelectedView::FeedView => app.feeds.previous(),
+                SelectedView::NewsView => app.news_data.as_mut().unwrap().previous(),
+            },
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Enter,
+            }) => match app.selected_view {
+                SelectedView::FeedView => app.view_feed_under_cursor(),
+                SelectedView::NewsView => app.view_news_under_cursor(),
+            },
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('r'),
+            }) => app.refresh_all(),
+            Event::Key(KeyEvent {
+                modifiers: KeyModifiers::NONE,
+                code: KeyCode::Char('o'),
+            }) => app.open_news_under_cursor(),
+            Event::AutoRefresh => app.refresh_all(),
+            Event::ConfigReloaded(new_config) => app.reload_config(new_config),
+            Event::Key(_) | Event::Resize(_, _) | Event::Tick | Event::NetworkDone => {}
         }
+        terminal.draw(|f| ui(f, &app))?;
     }
+    Ok(())
 }
 
 fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
     let channel_picker_screen = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .constraints(
+            [
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
         .split(f.size());
 
     let items: Vec<ListItem> = app
@@ -161,8 +252,24 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         .items
         .iter()
         .map(|i| {
-            let lines = vec![Spans::from(i.name.clone())];
-            ListItem::new(lines).style(Style::default().fg(Color::White))
+            let (glyph, color) = match &i.state {
+                FetchState::NotFetched => ("-", Color::DarkGray),
+                FetchState::Fetching => ("…", Color::Yellow),
+                FetchState::Fetched => ("✓", Color::Green),
+                FetchState::Failed(_) => ("✗", Color::Red),
+            };
+            let label = if i.item_keys.is_empty() {
+                format!("{} {}", glyph, i.feed.name)
+            } else {
+                let unread = i
+                    .item_keys
+                    .iter()
+                    .filter(|key| !app.history.is_read(key))
+                    .count();
+                format!("{} {} ({})", glyph, i.feed.name, unread)
+            };
+            let lines = vec![Spans::from(label)];
+            ListItem::new(lines).style(Style::default().fg(color))
         })
         .collect();
 
@@ -193,8 +300,13 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
     let mut news_items = Vec::<ListItem>::new();
     if let Some(data) = &app.news_data {
         for news in data.items.iter() {
+            let style = if app.history.is_read(&item_key(news)) {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            };
             let text = vec![Spans::from(String::from(news.title().unwrap()))];
-            news_items.push(ListItem::new(text).style(Style::default().fg(Color::White)));
+            news_items.push(ListItem::new(text).style(style));
         }
     };
 
@@ -236,17 +348,22 @@ fn ui<B: Backend>(f: &mut Frame<B>, app: &App) {
         }
         NavigationStack::Item => {
             if let Some(news_data) = &app.news_data {
-                let text = vec![Spans::from(
-                    news_data.items[app.news_index]
-                        .description
-                        .clone()
-                        .unwrap_or(String::from("No description")),
-                )];
-                let desc = Paragraph::new(text.clone())
+                let description = news_data.items[app.news_index]
+                    .description
+                    .clone()
+                    .unwrap_or(String::from("No description"));
+                let text = render_description(&description);
+                let desc = Paragraph::new(text)
                     .block(Block::default().borders(Borders::ALL))
                     .wrap(Wrap { trim: false });
                 f.render_widget(desc, channel_picker_screen[1]);
             }
         }
     }
+
+    if let Some(status) = &app.status {
+        let status_line = Paragraph::new(vec![Spans::from(status.clone())])
+            .style(Style::default().fg(Color::Red));
+        f.render_widget(status_line, channel_picker_screen[2]);
+    }
 }
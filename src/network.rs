@@ -1,46 +1,153 @@
-use crate::app::{App, Feed};
+use crate::app::{App, ConfigFeed, FetchState};
+use crate::Event;
 
+use futures::future::join_all;
 use rss::Channel;
+use std::process::{Command, Stdio};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
 
 pub enum IoEvent {
-    GetChannel(Feed),
+    GetChannel(String),
+    RefreshAll,
+    OpenItem(String),
 }
 
 pub struct Network<'a> {
     pub app: &'a Arc<Mutex<App>>,
+    event_tx: mpsc::Sender<Event>,
 }
 
 impl<'a> Network<'a> {
-    pub fn new(app: &'a Arc<Mutex<App>>) -> Network {
-        Network { app }
+    pub fn new(app: &'a Arc<Mutex<App>>, event_tx: mpsc::Sender<Event>) -> Network {
+        Network { app, event_tx }
     }
 
     pub async fn handle_network_event(&mut self, io_event: IoEvent) {
         match io_event {
-            IoEvent::GetChannel(feed) => {
-                self.get_channel(feed).await;
+            IoEvent::GetChannel(url) => {
+                self.get_channel(url).await;
+            }
+            IoEvent::RefreshAll => {
+                self.refresh_all().await;
+            }
+            IoEvent::OpenItem(link) => {
+                self.open_item(link).await;
             }
         }
-        let mut app = self.app.lock().await;
-        app.is_loading = false;
+        {
+            let mut app = self.app.lock().await;
+            app.is_loading = false;
+        }
+        let _ = self.event_tx.send(Event::NetworkDone).await;
     }
 
-    async fn get_channel(&mut self, feed: Feed) {
-        let result = reqwest::get(feed.url.clone()).await;
-        match result {
-            Ok(result) => match result.bytes().await {
-                Ok(result) => {
-                    let channel = Channel::read_from(&result[..]);
-                    let mut app = self.app.lock().await;
-                    let feed = Feed::new(feed.name.clone(), feed.url.clone());
-                    app.set_feed(channel.unwrap());
-                    app.selected_feed = Some(feed);
-                }
-                Err(_e) => {}
+    async fn fetch_channel(url: &str) -> Result<Channel, String> {
+        let bytes = reqwest::get(url)
+            .await
+            .map_err(|e| e.to_string())?
+            .bytes()
+            .await
+            .map_err(|e| e.to_string())?;
+        Channel::read_from(&bytes[..]).map_err(|e| e.to_string())
+    }
+
+    async fn get_channel(&mut self, url: String) {
+        let cached = {
+            let app = self.app.lock().await;
+            let ttl = Duration::from_secs(app.config.cache_ttl_secs);
+            app.fresh_cached_channel(&url, ttl)
+        };
+        if let Some(channel) = cached {
+            let mut app = self.app.lock().await;
+            app.set_news_data(&url, channel);
+            app.set_feed_state(&url, FetchState::Fetched);
+            return;
+        }
+
+        {
+            let mut app = self.app.lock().await;
+            app.set_feed_state(&url, FetchState::Fetching);
+        }
+        match Network::fetch_channel(&url).await {
+            Ok(channel) => {
+                let mut app = self.app.lock().await;
+                app.set_feed(&url, channel);
+                app.set_feed_state(&url, FetchState::Fetched);
+            }
+            Err(e) => {
+                let mut app = self.app.lock().await;
+                app.set_feed_state(&url, FetchState::Failed(e));
+            }
+        }
+    }
+
+    async fn open_item(&mut self, link: String) {
+        let open_command = {
+            let app = self.app.lock().await;
+            app.config.open_command.clone()
+        };
+
+        let result = match open_command {
+            Some(command) => Command::new(command)
+                .arg(&link)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn(),
+            None => match std::env::var("BROWSER") {
+                Ok(browser) => Command::new(browser)
+                    .arg(&link)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn(),
+                Err(_) => Command::new("xdg-open")
+                    .arg(&link)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .spawn(),
             },
-            Err(_e) => {}
+        };
+
+        if let Err(e) = result {
+            let mut app = self.app.lock().await;
+            app.status = Some(format!("Failed to open item: {}", e));
+        }
+    }
+
+    async fn refresh_all(&mut self) {
+        let feeds: Vec<ConfigFeed> = {
+            let app = self.app.lock().await;
+            app.config.feeds.clone()
+        };
+
+        {
+            let mut app = self.app.lock().await;
+            for feed in &feeds {
+                app.set_feed_state(&feed.url, FetchState::Fetching);
+            }
+        }
+
+        let fetches = feeds
+            .iter()
+            .map(|feed| async move { (feed.clone(), Network::fetch_channel(&feed.url).await) });
+        let results = join_all(fetches).await;
+
+        let mut app = self.app.lock().await;
+        for (feed, result) in results {
+            match result {
+                Ok(channel) => {
+                    app.set_feed_state(&feed.url, FetchState::Fetched);
+                    if app.is_selected_feed(&feed.url) {
+                        app.set_feed(&feed.url, channel);
+                    } else {
+                        app.cache_feed(&feed.url, channel);
+                    }
+                }
+                Err(e) => {
+                    app.set_feed_state(&feed.url, FetchState::Failed(e));
+                }
+            }
         }
     }
 }